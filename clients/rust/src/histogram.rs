@@ -0,0 +1,172 @@
+//! Fixed-memory latency histogram recorded in microseconds.
+//!
+//! Values are binned logarithmically into a flat `Vec<u64>` of counts
+//! (the same scheme lite-rpc's util-histogram uses) instead of accumulating
+//! every sample, so `record` is O(1) and percentile lookups never need to
+//! sort or clone the raw latencies. Each worker owns its own `Histogram`
+//! and the main loop `merge`s them together, since every histogram built
+//! with the same significant-figures/range shares an identical layout.
+
+use std::time::Duration;
+
+/// Significant decimal digits of precision to preserve at any magnitude.
+const DEFAULT_SIGNIFICANT_FIGURES: u8 = 3;
+
+/// Highest latency (in microseconds) the default histogram can represent;
+/// values above this are clamped into the top bucket.
+const DEFAULT_HIGHEST_TRACKABLE_VALUE_US: u64 = 60_000_000;
+
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    sub_bucket_count: u64,
+    sub_bucket_half_count_magnitude: u32,
+    highest_trackable_value: u64,
+    min: u64,
+    max: u64,
+    sum: u128,
+    total_count: u64,
+}
+
+impl Histogram {
+    /// A histogram sized for latencies up to one minute at 3 significant figures.
+    pub fn new() -> Self {
+        Self::with_precision(DEFAULT_SIGNIFICANT_FIGURES, DEFAULT_HIGHEST_TRACKABLE_VALUE_US)
+    }
+
+    /// A histogram with an explicit precision (significant decimal digits) and
+    /// highest trackable value (in microseconds).
+    pub fn with_precision(sig_figs: u8, highest_trackable_value: u64) -> Self {
+        let sub_bucket_count = (2 * 10u64.pow(sig_figs as u32)).next_power_of_two();
+        let sub_bucket_half_count_magnitude = sub_bucket_count.trailing_zeros() - 1;
+        let half = sub_bucket_count / 2;
+        let bucket_levels = Self::bucket_levels(highest_trackable_value, sub_bucket_count);
+        let counts_len = ((bucket_levels + 2) * half) as usize;
+
+        Self {
+            counts: vec![0; counts_len],
+            sub_bucket_count,
+            sub_bucket_half_count_magnitude,
+            highest_trackable_value,
+            min: u64::MAX,
+            max: 0,
+            sum: 0,
+            total_count: 0,
+        }
+    }
+
+    fn bucket_levels(highest_trackable_value: u64, sub_bucket_count: u64) -> u64 {
+        let mut smallest_untrackable_value = sub_bucket_count;
+        let mut levels = 0;
+        while smallest_untrackable_value <= highest_trackable_value {
+            smallest_untrackable_value <<= 1;
+            levels += 1;
+        }
+        levels
+    }
+
+    fn bucket_index(&self, value: u64) -> u32 {
+        let mask = self.sub_bucket_count - 1;
+        let bits = 64 - (value | mask).leading_zeros();
+        bits.saturating_sub(self.sub_bucket_half_count_magnitude + 1)
+    }
+
+    fn slot_for(&self, value: u64) -> usize {
+        let bucket_index = self.bucket_index(value) as u64;
+        let sub_bucket_index = value >> bucket_index;
+        let half = self.sub_bucket_count / 2;
+        (bucket_index * half + sub_bucket_index) as usize
+    }
+
+    fn value_for_slot(&self, slot: u64) -> u64 {
+        let half = self.sub_bucket_count / 2;
+        if slot < 2 * half {
+            slot
+        } else {
+            let bucket_index = slot / half - 1;
+            let sub_bucket_index = slot - bucket_index * half;
+            sub_bucket_index << bucket_index
+        }
+    }
+
+    /// Record a latency in microseconds. O(1): increments one counter slot.
+    pub fn record(&mut self, value_us: u64) {
+        let value = value_us.min(self.highest_trackable_value);
+        let slot = self.slot_for(value);
+        if slot >= self.counts.len() {
+            self.counts.resize(slot + 1, 0);
+        }
+        self.counts[slot] += 1;
+        self.total_count += 1;
+        self.sum += value_us as u128;
+        self.min = self.min.min(value_us);
+        self.max = self.max.max(value_us);
+    }
+
+    /// Merge another histogram's counts into this one. Requires both histograms
+    /// to share the same precision/range, which holds for all histograms created
+    /// the same way by each worker.
+    pub fn merge(&mut self, other: &Histogram) {
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (slot, count) in other.counts.iter().enumerate() {
+            self.counts[slot] += count;
+        }
+        self.total_count += other.total_count;
+        self.sum += other.sum;
+        if other.total_count > 0 {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+    }
+
+    /// The `p`th percentile latency (0.0..=100.0), e.g. `percentile(99.0)` for p99.
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.total_count == 0 {
+            return Duration::ZERO;
+        }
+        let target = (((p / 100.0) * self.total_count as f64).ceil() as u64).max(1);
+        let mut cumulative = 0u64;
+        for (slot, count) in self.counts.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+            cumulative += count;
+            if cumulative >= target {
+                return Duration::from_micros(self.value_for_slot(slot as u64));
+            }
+        }
+        Duration::from_micros(self.max)
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.total_count == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_micros((self.sum / self.total_count as u128) as u64)
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        if self.total_count == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_micros(self.min)
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.total_count
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
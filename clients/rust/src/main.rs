@@ -1,19 +1,31 @@
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use clap::Parser;
 use deadpool_postgres::{Config as PoolConfig, Pool, Runtime};
-use futures::stream::StreamExt;
+use futures::stream::{FuturesUnordered, StreamExt};
+use native_tls::{Certificate as NativeCertificate, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 use serde::Deserialize;
-use sysinfo::System;
-use tokio::sync::mpsc;
-use tokio::time::interval;
+use tokio_postgres::config::SslMode;
 use tokio_postgres::NoTls;
-use tonic::transport::Channel;
+use tokio_util::sync::CancellationToken;
+use tonic::transport::{Certificate as GrpcCertificate, Channel, ClientTlsConfig};
+
+mod histogram;
+mod metrics;
+mod shutdown;
+mod workpool;
+
+use histogram::Histogram;
+use metrics::Metrics;
+use shutdown::Shutdown;
+use workpool::{Results, WorkerPool};
 
 pub mod benchmark {
     tonic::include_proto!("benchmark");
@@ -43,11 +55,15 @@ struct Args {
     #[arg(long, default_value = "30s")]
     duration: String,
 
-    /// Events per second for streaming (0 = unlimited)
+    /// Events/s for streaming, or target open-loop request rate for balance (0 = closed-loop/unlimited)
     #[arg(long, default_value_t = 0)]
     rate: i32,
 
-    /// gRPC server address
+    /// Address to serve live Prometheus metrics on, e.g. 127.0.0.1:9090 (disabled if unset)
+    #[arg(long)]
+    metrics_addr: Option<String>,
+
+    /// gRPC server address; use an https:// scheme to connect over TLS
     #[arg(long, default_value = "http://localhost:50051")]
     grpc_addr: String,
 
@@ -55,6 +71,14 @@ struct Args {
     #[arg(long, default_value = "http://localhost:8080")]
     rest_addr: String,
 
+    /// Root CA certificate (PEM) to trust when --grpc-addr uses https://
+    #[arg(long)]
+    grpc_tls_ca: Option<String>,
+
+    /// TLS domain name to verify the gRPC server's certificate against, if it differs from the host in --grpc-addr
+    #[arg(long)]
+    grpc_tls_domain: Option<String>,
+
     /// PostgreSQL host
     #[arg(long, default_value = "localhost")]
     db_host: String,
@@ -74,21 +98,14 @@ struct Args {
     /// PostgreSQL database
     #[arg(long, default_value = "grpc_benchmark")]
     db_name: String,
-}
 
-#[derive(Debug)]
-struct Sample {
-    latency: Duration,
-    success: bool,
-}
+    /// PostgreSQL SSL mode: disable | prefer | require
+    #[arg(long, default_value = "disable")]
+    db_sslmode: String,
 
-#[derive(Debug, Default)]
-struct Results {
-    samples: Vec<Sample>,
-    start_time: Option<Instant>,
-    end_time: Option<Instant>,
-    cpu_samples: Vec<f32>,
-    mem_samples: Vec<u64>,
+    /// Root CA certificate (PEM) to trust for Postgres when --db-sslmode is prefer/require
+    #[arg(long)]
+    db_ca_cert: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -99,118 +116,6 @@ struct BalanceResponse {
     balance_tinybar: i64,
 }
 
-impl Results {
-    fn add_sample(&mut self, sample: Sample) {
-        self.samples.push(sample);
-    }
-
-    fn success_count(&self) -> usize {
-        self.samples.iter().filter(|s| s.success).count()
-    }
-
-    fn error_count(&self) -> usize {
-        self.samples.iter().filter(|s| !s.success).count()
-    }
-
-    fn latencies(&self) -> Vec<Duration> {
-        self.samples
-            .iter()
-            .filter(|s| s.success)
-            .map(|s| s.latency)
-            .collect()
-    }
-
-    fn percentile(&self, p: f64) -> Duration {
-        let mut latencies = self.latencies();
-        if latencies.is_empty() {
-            return Duration::ZERO;
-        }
-        latencies.sort();
-        let idx = ((p / 100.0) * latencies.len() as f64) as usize;
-        latencies[idx.min(latencies.len() - 1)]
-    }
-
-    fn avg_latency(&self) -> Duration {
-        let latencies = self.latencies();
-        if latencies.is_empty() {
-            return Duration::ZERO;
-        }
-        let total: Duration = latencies.iter().sum();
-        total / latencies.len() as u32
-    }
-
-    fn min_latency(&self) -> Duration {
-        self.latencies().into_iter().min().unwrap_or(Duration::ZERO)
-    }
-
-    fn max_latency(&self) -> Duration {
-        self.latencies().into_iter().max().unwrap_or(Duration::ZERO)
-    }
-
-    fn throughput(&self) -> f64 {
-        match (self.start_time, self.end_time) {
-            (Some(start), Some(end)) => {
-                let duration = end.duration_since(start).as_secs_f64();
-                if duration > 0.0 {
-                    self.success_count() as f64 / duration
-                } else {
-                    0.0
-                }
-            }
-            _ => 0.0,
-        }
-    }
-
-    fn avg_cpu(&self) -> f32 {
-        if self.cpu_samples.is_empty() {
-            return 0.0;
-        }
-        self.cpu_samples.iter().sum::<f32>() / self.cpu_samples.len() as f32
-    }
-
-    fn avg_mem_mb(&self) -> f64 {
-        if self.mem_samples.is_empty() {
-            return 0.0;
-        }
-        let avg_bytes = self.mem_samples.iter().sum::<u64>() as f64 / self.mem_samples.len() as f64;
-        avg_bytes / 1024.0 / 1024.0
-    }
-
-    fn peak_mem_mb(&self) -> f64 {
-        self.mem_samples.iter().max().copied().unwrap_or(0) as f64 / 1024.0 / 1024.0
-    }
-
-    fn print_summary(&self, scenario: &str, protocol: &str, concurrency: usize) {
-        let duration = match (self.start_time, self.end_time) {
-            (Some(start), Some(end)) => end.duration_since(start),
-            _ => Duration::ZERO,
-        };
-
-        println!();
-        println!("Benchmark: {} / {}", scenario, protocol);
-        println!("Duration: {:?} | Concurrency: {}", duration, concurrency);
-        println!("---------------------------------");
-        println!("Requests:    {}", self.samples.len());
-        println!("Throughput:  {:.2} req/s", self.throughput());
-        println!("Latency:");
-        println!("  p50:  {:?}", self.percentile(50.0));
-        println!("  p90:  {:?}", self.percentile(90.0));
-        println!("  p99:  {:?}", self.percentile(99.0));
-        println!("  avg:  {:?}", self.avg_latency());
-        println!("  min:  {:?}", self.min_latency());
-        println!("  max:  {:?}", self.max_latency());
-        println!(
-            "Errors:      {} ({:.2}%)",
-            self.error_count(),
-            self.error_count() as f64 / self.samples.len().max(1) as f64 * 100.0
-        );
-        println!("Resources:");
-        println!("  CPU avg:   {:.1}%", self.avg_cpu());
-        println!("  Mem avg:   {:.1} MB", self.avg_mem_mb());
-        println!("  Mem peak:  {:.1} MB", self.peak_mem_mb());
-    }
-}
-
 fn parse_duration(s: &str) -> Result<Duration, String> {
     let s = s.trim();
     if s.ends_with("ms") {
@@ -227,14 +132,69 @@ fn parse_duration(s: &str) -> Result<Duration, String> {
     }
 }
 
-/// Create a database connection pool with retry logic.
+/// A Postgres pool, plaintext or TLS. `deadpool_postgres::Pool` is generic
+/// over its TLS connector, so the two modes are different concrete types
+/// upstream and have to be matched on here rather than unified behind one
+/// type parameter.
+enum DbPool {
+    Plain(Pool),
+    Tls(deadpool_postgres::Pool<MakeTlsConnector>),
+}
+
+impl DbPool {
+    async fn get(&self) -> Result<DbClient, deadpool_postgres::PoolError> {
+        Ok(match self {
+            DbPool::Plain(pool) => DbClient::Plain(pool.get().await?),
+            DbPool::Tls(pool) => DbClient::Tls(pool.get().await?),
+        })
+    }
+}
+
+/// A checked-out connection from either pool variant above.
+enum DbClient {
+    Plain(deadpool_postgres::Object),
+    Tls(deadpool_postgres::Object<MakeTlsConnector>),
+}
+
+impl DbClient {
+    async fn query(
+        &self,
+        statement: &str,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) -> Result<Vec<tokio_postgres::Row>, tokio_postgres::Error> {
+        match self {
+            DbClient::Plain(c) => c.query(statement, params).await,
+            DbClient::Tls(c) => c.query(statement, params).await,
+        }
+    }
+
+    async fn query_one(
+        &self,
+        statement: &str,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) -> Result<tokio_postgres::Row, tokio_postgres::Error> {
+        match self {
+            DbClient::Plain(c) => c.query_one(statement, params).await,
+            DbClient::Tls(c) => c.query_one(statement, params).await,
+        }
+    }
+}
+
+/// Create a database connection pool. `sslmode` of
+/// "disable" connects in plaintext; "prefer" and "require" both negotiate
+/// TLS via `postgres-native-tls`, trusting `ca_cert_path` as an additional
+/// root certificate when given. "require" sets `SslMode::Require` so the
+/// connection fails closed instead of silently downgrading to plaintext if
+/// the server declines the SSLRequest, the way "prefer" does.
 fn create_db_pool(
     db_host: &str,
     db_port: u16,
     db_user: &str,
     db_pass: &str,
     db_name: &str,
-) -> Result<Pool, Box<dyn std::error::Error>> {
+    sslmode: &str,
+    ca_cert_path: Option<&str>,
+) -> Result<DbPool, Box<dyn std::error::Error>> {
     let mut cfg = PoolConfig::new();
     cfg.host = Some(db_host.to_string());
     cfg.port = Some(db_port);
@@ -249,12 +209,29 @@ fn create_db_pool(
         ..Default::default()
     });
 
-    let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
-    Ok(pool)
+    if sslmode == "disable" {
+        let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+        return Ok(DbPool::Plain(pool));
+    }
+
+    cfg.ssl_mode = Some(if sslmode == "require" {
+        SslMode::Require
+    } else {
+        SslMode::Prefer
+    });
+
+    let mut builder = TlsConnector::builder();
+    if let Some(path) = ca_cert_path {
+        let pem = std::fs::read(path)?;
+        builder.add_root_certificate(NativeCertificate::from_pem(&pem)?);
+    }
+    let connector = MakeTlsConnector::new(builder.build()?);
+    let pool = cfg.create_pool(Some(Runtime::Tokio1), connector)?;
+    Ok(DbPool::Tls(pool))
 }
 
 /// Connect to database with retry logic.
-async fn connect_with_retry(pool: &Pool, max_retries: u32) -> Result<deadpool_postgres::Object, Box<dyn std::error::Error>> {
+async fn connect_with_retry(pool: &DbPool, max_retries: u32) -> Result<DbClient, Box<dyn std::error::Error>> {
     let mut last_err = None;
     let mut retry_interval = Duration::from_millis(100);
 
@@ -275,7 +252,7 @@ async fn connect_with_retry(pool: &Pool, max_retries: u32) -> Result<deadpool_po
     Err(format!("Failed to connect after {} retries: {:?}", max_retries, last_err).into())
 }
 
-async fn fetch_account_ids(pool: &Pool) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+async fn fetch_account_ids(pool: &DbPool) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let client = connect_with_retry(pool, 3).await?;
 
     let rows = client
@@ -287,7 +264,7 @@ async fn fetch_account_ids(pool: &Pool) -> Result<Vec<String>, Box<dyn std::erro
 }
 
 async fn store_results(
-    pool: &Pool,
+    pool: &DbPool,
     scenario: &str,
     protocol: &str,
     concurrency: usize,
@@ -300,13 +277,16 @@ async fn store_results(
         _ => Duration::ZERO,
     };
 
-    // Insert run record
+    // Insert run record. Latencies now live in per-worker histograms rather
+    // than a per-sample Vec, so we persist the aggregate percentiles instead
+    // of a `benchmark_samples` row per request.
     let client_name = format!("rust-{}", protocol);
     let row = client
         .query_one(
             "INSERT INTO benchmark_runs (scenario, protocol, client, concurrency, duration_sec, \
-             cpu_usage_avg, memory_mb_avg, memory_mb_peak) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+             requests_total, requests_failed, latency_p50_ms, latency_p90_ms, latency_p99_ms, \
+             latency_avg_ms, cpu_usage_avg, memory_mb_avg, memory_mb_peak) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) \
              RETURNING id",
             &[
                 &scenario,
@@ -314,6 +294,12 @@ async fn store_results(
                 &client_name.as_str(),
                 &(concurrency as i32),
                 &(duration.as_secs() as i32),
+                &(results.success_count() as i64 + results.error_count() as i64),
+                &(results.error_count() as i64),
+                &(results.percentile(50.0).as_secs_f64() * 1000.0),
+                &(results.percentile(90.0).as_secs_f64() * 1000.0),
+                &(results.percentile(99.0).as_secs_f64() * 1000.0),
+                &(results.avg_latency().as_secs_f64() * 1000.0),
                 &(results.avg_cpu() as f64),
                 &results.avg_mem_mb(),
                 &results.peak_mem_mb(),
@@ -323,330 +309,394 @@ async fn store_results(
 
     let run_id: i32 = row.get(0);
 
-    // Insert samples (batch insert for performance)
-    let now = chrono::Utc::now().naive_utc();
-    for chunk in results.samples.chunks(1000) {
-        let mut query = String::from(
-            "INSERT INTO benchmark_samples (run_id, latency_ms, success, timestamp) VALUES "
-        );
-        let mut values: Vec<String> = Vec::new();
-        for (i, _sample) in chunk.iter().enumerate() {
-            let idx = i * 4;
-            values.push(format!(
-                "(${}, ${}, ${}, ${})",
-                idx + 1, idx + 2, idx + 3, idx + 4
-            ));
-        }
-        query.push_str(&values.join(", "));
-
-        // Build params
-        let mut params: Vec<Box<dyn tokio_postgres::types::ToSql + Sync>> = Vec::new();
-        for sample in chunk {
-            let latency_ms = sample.latency.as_secs_f64() * 1000.0;
-            params.push(Box::new(run_id));
-            params.push(Box::new(latency_ms));
-            params.push(Box::new(sample.success));
-            params.push(Box::new(now));
-        }
-        let params_refs: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
-            params.iter().map(|p| p.as_ref()).collect();
-        client.execute(&query, &params_refs).await?;
-    }
-
     println!("\nResults saved to database (run_id: {})", run_id);
 
     Ok(run_id)
 }
 
-async fn run_grpc_balance(
-    addr: &str,
-    account_ids: Vec<String>,
-    concurrency: usize,
-    duration: Duration,
-) -> Results {
-    let mut results = Results::default();
-    let (tx, mut rx) = mpsc::channel::<Sample>(10000);
-    let running = Arc::new(AtomicBool::new(true));
-    let request_count = Arc::new(AtomicU64::new(0));
-
-    // Start resource monitoring
-    let cpu_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let mem_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let monitor_running = running.clone();
-    let cpu_samples_clone = cpu_samples.clone();
-    let mem_samples_clone = mem_samples.clone();
-
-    tokio::spawn(async move {
-        let mut sys = System::new_all();
-        let pid = sysinfo::get_current_pid().unwrap();
-        let mut interval = interval(Duration::from_millis(100));
-
-        while monitor_running.load(Ordering::Relaxed) {
-            interval.tick().await;
-            sys.refresh_all();
-
-            if let Some(process) = sys.process(pid) {
-                cpu_samples_clone.lock().await.push(process.cpu_usage());
-                mem_samples_clone.lock().await.push(process.memory());
-            }
-        }
-    });
-
-    results.start_time = Some(Instant::now());
-
-    // Spawn workers
-    for _ in 0..concurrency {
-        let tx = tx.clone();
-        let addr = addr.to_string();
-        let account_ids = account_ids.clone();
-        let running = running.clone();
-        let request_count = request_count.clone();
-
-        tokio::spawn(async move {
-            let channel = match Channel::from_shared(addr.clone()) {
-                Ok(c) => match c.connect().await {
-                    Ok(ch) => ch,
-                    Err(e) => {
-                        eprintln!("Failed to connect: {}", e);
-                        return;
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Invalid URI: {}", e);
-                    return;
-                }
-            };
+/// TLS settings for the gRPC channel, built once in `main` and cloned into
+/// each worker. Empty/`None` fields mean "use the transport's defaults".
+#[derive(Clone, Default)]
+struct GrpcTlsConfig {
+    ca_cert: Option<Vec<u8>>,
+    domain: Option<String>,
+}
 
-            let mut client = BalanceServiceClient::new(channel);
-            let mut rng = StdRng::from_entropy();
+/// Connect to `addr`, negotiating TLS via `tls` when the scheme is `https://`.
+async fn connect_grpc_channel(addr: &str, tls: &GrpcTlsConfig) -> Result<Channel, String> {
+    let endpoint = Channel::from_shared(addr.to_string()).map_err(|e| format!("Invalid URI: {}", e))?;
 
-            while running.load(Ordering::Relaxed) {
-                let account_id = account_ids.choose(&mut rng).unwrap().clone();
-                let start = Instant::now();
+    let endpoint = if addr.starts_with("https://") {
+        let mut tls_config = ClientTlsConfig::new();
+        if let Some(ca_cert) = &tls.ca_cert {
+            tls_config = tls_config.ca_certificate(GrpcCertificate::from_pem(ca_cert));
+        }
+        if let Some(domain) = &tls.domain {
+            tls_config = tls_config.domain_name(domain.clone());
+        }
+        endpoint
+            .tls_config(tls_config)
+            .map_err(|e| format!("Invalid TLS config: {}", e))?
+    } else {
+        endpoint
+    };
 
-                let result = client
-                    .get_balance(BalanceRequest { account_id })
-                    .await;
+    endpoint.connect().await.map_err(|e| format!("Failed to connect: {}", e))
+}
 
-                let latency = start.elapsed();
-                let success = result.is_ok();
+/// Shared open-loop scheduler: fires requests at a fixed cadence independent
+/// of response timing, instead of waiting for each response before sending
+/// the next. This worker is one of `concurrency` evenly-phased slots that
+/// together issue requests at the aggregate `rate`; slot `worker_index`
+/// targets `run_start + worker_index/rate + k * (concurrency/rate)` for its
+/// k-th request. Latency is measured against that intended start time, not
+/// the actual send time, so queueing delay incurred while the client is
+/// behind schedule (coordinated omission) is captured rather than hidden.
+/// `dispatch` issues one request against `intended_start` and reports
+/// `(latency, succeeded)`; each tick spawns it so one slow response can't
+/// delay the next scheduled send, and finished dispatches are drained
+/// opportunistically alongside the scheduling loop rather than collected
+/// for a `join_all` at the end, so in-flight task handles don't pile up over
+/// a long high-rate run.
+async fn open_loop_scheduler<F, Fut>(
+    worker_index: usize,
+    concurrency: usize,
+    rate: i32,
+    token: CancellationToken,
+    metrics: Option<Arc<Metrics>>,
+    dispatch: F,
+) -> (Histogram, Histogram)
+where
+    F: Fn(tokio::time::Instant) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = (Duration, bool)> + Send + 'static,
+{
+    let success = Arc::new(tokio::sync::Mutex::new(Histogram::new()));
+    let errors = Arc::new(tokio::sync::Mutex::new(Histogram::new()));
+    let dispatch = Arc::new(dispatch);
+    let mut pending = FuturesUnordered::new();
+
+    let period = Duration::from_secs_f64(concurrency as f64 / rate as f64);
+    let mut next_start =
+        tokio::time::Instant::now() + Duration::from_secs_f64(worker_index as f64 / rate as f64);
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tokio::time::sleep_until(next_start) => {
+                let intended_start = next_start;
+                next_start += period;
+
+                let dispatch = dispatch.clone();
+                let success = success.clone();
+                let errors = errors.clone();
+                let metrics = metrics.clone();
+
+                pending.push(tokio::spawn(async move {
+                    let (latency, succeeded) = dispatch(intended_start).await;
+                    if let Some(metrics) = &metrics {
+                        metrics.record(latency, succeeded);
+                    }
 
-                request_count.fetch_add(1, Ordering::Relaxed);
-                let _ = tx.send(Sample { latency, success }).await;
+                    let latency_us = latency.as_micros() as u64;
+                    if succeeded {
+                        success.lock().await.record(latency_us);
+                    } else {
+                        errors.lock().await.record(latency_us);
+                    }
+                }));
             }
-        });
-    }
-
-    drop(tx); // Drop original sender so channel closes when workers stop
-
-    // Run for duration
-    tokio::time::sleep(duration).await;
-    running.store(false, Ordering::Relaxed);
-
-    // Collect results
-    while let Some(sample) = rx.recv().await {
-        results.add_sample(sample);
+            Some(_) = pending.next(), if !pending.is_empty() => {}
+        }
     }
 
-    results.end_time = Some(Instant::now());
-    results.cpu_samples = cpu_samples.lock().await.clone();
-    results.mem_samples = mem_samples.lock().await.clone();
+    while pending.next().await.is_some() {}
 
-    results
+    let success = Arc::try_unwrap(success).expect("all senders dropped").into_inner();
+    let errors = Arc::try_unwrap(errors).expect("all senders dropped").into_inner();
+    (success, errors)
 }
 
-async fn run_rest_balance(
-    base_url: &str,
-    account_ids: Vec<String>,
+/// Open-loop balance scheduler for the gRPC protocol (see `open_loop_scheduler` for the schedule).
+async fn open_loop_balance(
+    client: BalanceServiceClient<Channel>,
+    worker_index: usize,
     concurrency: usize,
-    duration: Duration,
-) -> Results {
-    let mut results = Results::default();
-    let (tx, mut rx) = mpsc::channel::<Sample>(10000);
-    let running = Arc::new(AtomicBool::new(true));
-
-    // Start resource monitoring
-    let cpu_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let mem_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let monitor_running = running.clone();
-    let cpu_samples_clone = cpu_samples.clone();
-    let mem_samples_clone = mem_samples.clone();
-
-    tokio::spawn(async move {
-        let mut sys = System::new_all();
-        let pid = sysinfo::get_current_pid().unwrap();
-        let mut interval = interval(Duration::from_millis(100));
-
-        while monitor_running.load(Ordering::Relaxed) {
-            interval.tick().await;
-            sys.refresh_all();
-
-            if let Some(process) = sys.process(pid) {
-                cpu_samples_clone.lock().await.push(process.cpu_usage());
-                mem_samples_clone.lock().await.push(process.memory());
-            }
-        }
-    });
-
-    results.start_time = Some(Instant::now());
-
-    // Spawn workers
-    for _ in 0..concurrency {
-        let tx = tx.clone();
-        let base_url = base_url.to_string();
+    rate: i32,
+    account_ids: Vec<String>,
+    token: CancellationToken,
+    metrics: Option<Arc<Metrics>>,
+) -> (Histogram, Histogram) {
+    let account_ids = Arc::new(account_ids);
+    let rng = Arc::new(tokio::sync::Mutex::new(StdRng::from_entropy()));
+
+    open_loop_scheduler(worker_index, concurrency, rate, token, metrics, move |intended_start| {
+        let mut client = client.clone();
         let account_ids = account_ids.clone();
-        let running = running.clone();
-
-        tokio::spawn(async move {
-            let client = reqwest::Client::builder()
-                .pool_max_idle_per_host(100)
-                .build()
-                .unwrap();
-            let mut rng = StdRng::from_entropy();
-
-            while running.load(Ordering::Relaxed) {
-                let account_id = account_ids.choose(&mut rng).unwrap();
-                let url = format!("{}/api/v1/accounts/{}/balance", base_url, account_id);
-                let start = Instant::now();
-
-                let result = client.get(&url).send().await;
-
-                let latency = start.elapsed();
-                let success = match result {
-                    Ok(resp) => resp.status().is_success(),
-                    Err(_) => false,
-                };
-
-                let _ = tx.send(Sample { latency, success }).await;
-            }
-        });
-    }
-
-    drop(tx);
-
-    // Run for duration
-    tokio::time::sleep(duration).await;
-    running.store(false, Ordering::Relaxed);
-
-    // Collect results
-    while let Some(sample) = rx.recv().await {
-        results.add_sample(sample);
-    }
-
-    results.end_time = Some(Instant::now());
-    results.cpu_samples = cpu_samples.lock().await.clone();
-    results.mem_samples = mem_samples.lock().await.clone();
-
-    results
+        let rng = rng.clone();
+        async move {
+            let account_id = {
+                let mut rng = rng.lock().await;
+                account_ids.choose(&mut *rng).unwrap().clone()
+            };
+            let result = client.get_balance(BalanceRequest { account_id }).await;
+            (intended_start.elapsed(), result.is_ok())
+        }
+    })
+    .await
 }
 
-async fn run_grpc_stream(
+async fn run_grpc_balance(
     addr: &str,
+    account_ids: Vec<String>,
     concurrency: usize,
-    duration: Duration,
+    shutdown: &Shutdown,
     rate: i32,
+    metrics: Option<Arc<Metrics>>,
+    tls: GrpcTlsConfig,
 ) -> Results {
-    let mut results = Results::default();
-    let (tx, mut rx) = mpsc::channel::<Sample>(10000);
-    let running = Arc::new(AtomicBool::new(true));
-
-    // Start resource monitoring
-    let cpu_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let mem_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-    let monitor_running = running.clone();
-    let cpu_samples_clone = cpu_samples.clone();
-    let mem_samples_clone = mem_samples.clone();
-
-    tokio::spawn(async move {
-        let mut sys = System::new_all();
-        let pid = sysinfo::get_current_pid().unwrap();
-        let mut interval = interval(Duration::from_millis(100));
-
-        while monitor_running.load(Ordering::Relaxed) {
-            interval.tick().await;
-            sys.refresh_all();
-
-            if let Some(process) = sys.process(pid) {
-                cpu_samples_clone.lock().await.push(process.cpu_usage());
-                mem_samples_clone.lock().await.push(process.memory());
-            }
-        }
-    });
+    let addr = addr.to_string();
+
+    WorkerPool::new(concurrency)
+        .run(
+            shutdown,
+            move |_worker_index| {
+                let addr = addr.clone();
+                let tls = tls.clone();
+                async move { connect_grpc_channel(&addr, &tls).await.map(BalanceServiceClient::new) }
+            },
+            move |worker_index, client, token| {
+                let account_ids = account_ids.clone();
+                let metrics = metrics.clone();
+                async move {
+                    if rate > 0 {
+                        return open_loop_balance(
+                            client,
+                            worker_index,
+                            concurrency,
+                            rate,
+                            account_ids,
+                            token,
+                            metrics,
+                        )
+                        .await;
+                    }
 
-    results.start_time = Some(Instant::now());
-
-    // Spawn stream workers
-    for _ in 0..concurrency {
-        let tx = tx.clone();
-        let addr = addr.to_string();
-        let running = running.clone();
-
-        tokio::spawn(async move {
-            let channel = match Channel::from_shared(addr.clone()) {
-                Ok(c) => match c.connect().await {
-                    Ok(ch) => ch,
-                    Err(e) => {
-                        eprintln!("Failed to connect: {}", e);
-                        return;
+                    let mut success = Histogram::new();
+                    let mut errors = Histogram::new();
+                    let mut rng = StdRng::from_entropy();
+
+                    while !token.is_cancelled() {
+                        let account_id = account_ids.choose(&mut rng).unwrap().clone();
+                        let start = Instant::now();
+                        let mut client = client.clone();
+
+                        let result = client.get_balance(BalanceRequest { account_id }).await;
+
+                        let latency = start.elapsed();
+                        let succeeded = result.is_ok();
+                        if let Some(metrics) = &metrics {
+                            metrics.record(latency, succeeded);
+                        }
+
+                        let latency_us = latency.as_micros() as u64;
+                        if succeeded {
+                            success.record(latency_us);
+                        } else {
+                            errors.record(latency_us);
+                        }
                     }
-                },
-                Err(e) => {
-                    eprintln!("Invalid URI: {}", e);
-                    return;
+
+                    (success, errors)
                 }
-            };
+            },
+        )
+        .await
+}
 
-            let mut client = TransactionServiceClient::new(channel);
-            let request = StreamRequest {
-                since_timestamp: String::new(),
-                rate_limit: rate,
-                filter_account: String::new(),
+/// Open-loop counterpart to `open_loop_balance` for the REST protocol (see `open_loop_scheduler` for the schedule).
+async fn open_loop_rest_balance(
+    client: reqwest::Client,
+    base_url: String,
+    worker_index: usize,
+    concurrency: usize,
+    rate: i32,
+    account_ids: Vec<String>,
+    token: CancellationToken,
+    metrics: Option<Arc<Metrics>>,
+) -> (Histogram, Histogram) {
+    let base_url = Arc::new(base_url);
+    let account_ids = Arc::new(account_ids);
+    let rng = Arc::new(tokio::sync::Mutex::new(StdRng::from_entropy()));
+
+    open_loop_scheduler(worker_index, concurrency, rate, token, metrics, move |intended_start| {
+        let client = client.clone();
+        let base_url = base_url.clone();
+        let account_ids = account_ids.clone();
+        let rng = rng.clone();
+        async move {
+            let account_id = {
+                let mut rng = rng.lock().await;
+                account_ids.choose(&mut *rng).unwrap().clone()
             };
+            let url = format!("{}/api/v1/accounts/{}/balance", base_url, account_id);
 
-            let mut stream = match client.stream_transactions(request).await {
-                Ok(response) => response.into_inner(),
-                Err(e) => {
-                    eprintln!("Failed to start stream: {}", e);
-                    return;
-                }
+            let result = client.get(&url).send().await;
+            let succeeded = match result {
+                Ok(resp) => resp.status().is_success(),
+                Err(_) => false,
             };
+            (intended_start.elapsed(), succeeded)
+        }
+    })
+    .await
+}
 
-            let mut last_event = Instant::now();
-            while running.load(Ordering::Relaxed) {
-                match stream.next().await {
-                    Some(Ok(_)) => {
-                        let now = Instant::now();
-                        let latency = now.duration_since(last_event);
-                        last_event = now;
-
-                        let _ = tx.send(Sample { latency, success: true }).await;
-                    }
-                    Some(Err(e)) => {
-                        eprintln!("Stream error: {}", e);
-                        break;
+async fn run_rest_balance(
+    base_url: &str,
+    account_ids: Vec<String>,
+    concurrency: usize,
+    shutdown: &Shutdown,
+    rate: i32,
+    metrics: Option<Arc<Metrics>>,
+) -> Results {
+    let base_url = base_url.to_string();
+
+    WorkerPool::new(concurrency)
+        .run(
+            shutdown,
+            |_worker_index| async move {
+                reqwest::Client::builder()
+                    .pool_max_idle_per_host(100)
+                    .build()
+                    .map_err(|e| format!("Failed to build REST client: {}", e))
+            },
+            move |worker_index, client, token| {
+                let base_url = base_url.clone();
+                let account_ids = account_ids.clone();
+                let metrics = metrics.clone();
+                async move {
+                    if rate > 0 {
+                        return open_loop_rest_balance(
+                            client,
+                            base_url,
+                            worker_index,
+                            concurrency,
+                            rate,
+                            account_ids,
+                            token,
+                            metrics,
+                        )
+                        .await;
                     }
-                    None => break,
-                }
-            }
-        });
-    }
 
-    drop(tx);
-
-    // Run for duration
-    tokio::time::sleep(duration).await;
-    running.store(false, Ordering::Relaxed);
+                    let mut success = Histogram::new();
+                    let mut errors = Histogram::new();
+                    let mut rng = StdRng::from_entropy();
+
+                    while !token.is_cancelled() {
+                        let account_id = account_ids.choose(&mut rng).unwrap();
+                        let url = format!("{}/api/v1/accounts/{}/balance", base_url, account_id);
+                        let start = Instant::now();
+
+                        let result = client.get(&url).send().await;
+
+                        let latency = start.elapsed();
+                        let succeeded = match result {
+                            Ok(resp) => resp.status().is_success(),
+                            Err(_) => false,
+                        };
+                        if let Some(metrics) = &metrics {
+                            metrics.record(latency, succeeded);
+                        }
+
+                        let latency_us = latency.as_micros() as u64;
+                        if succeeded {
+                            success.record(latency_us);
+                        } else {
+                            errors.record(latency_us);
+                        }
+                    }
 
-    // Collect results
-    while let Some(sample) = rx.recv().await {
-        results.add_sample(sample);
-    }
+                    (success, errors)
+                }
+            },
+        )
+        .await
+}
 
-    results.end_time = Some(Instant::now());
-    results.cpu_samples = cpu_samples.lock().await.clone();
-    results.mem_samples = mem_samples.lock().await.clone();
+async fn run_grpc_stream(
+    addr: &str,
+    concurrency: usize,
+    shutdown: &Shutdown,
+    rate: i32,
+    metrics: Option<Arc<Metrics>>,
+    tls: GrpcTlsConfig,
+) -> Results {
+    let addr = addr.to_string();
+
+    WorkerPool::new(concurrency)
+        .run(
+            shutdown,
+            move |_worker_index| {
+                let addr = addr.clone();
+                let tls = tls.clone();
+                async move { connect_grpc_channel(&addr, &tls).await.map(TransactionServiceClient::new) }
+            },
+            move |_worker_index, mut client: TransactionServiceClient<Channel>, token| {
+                let metrics = metrics.clone();
+                async move {
+                    let mut success = Histogram::new();
+                    let mut errors = Histogram::new();
+
+                    let request = StreamRequest {
+                        since_timestamp: String::new(),
+                        rate_limit: rate,
+                        filter_account: String::new(),
+                    };
+
+                    let mut stream = match client.stream_transactions(request).await {
+                        Ok(response) => response.into_inner(),
+                        Err(e) => {
+                            eprintln!("Failed to start stream: {}", e);
+                            if let Some(metrics) = &metrics {
+                                metrics.record(Duration::ZERO, false);
+                            }
+                            errors.record(0);
+                            return (success, errors);
+                        }
+                    };
+
+                    let mut last_event = Instant::now();
+                    loop {
+                        let event = tokio::select! {
+                            _ = token.cancelled() => break,
+                            event = stream.next() => event,
+                        };
+
+                        match event {
+                            Some(Ok(_)) => {
+                                let now = Instant::now();
+                                let latency = now.duration_since(last_event);
+                                last_event = now;
+
+                                if let Some(metrics) = &metrics {
+                                    metrics.record(latency, true);
+                                }
+                                success.record(latency.as_micros() as u64);
+                            }
+                            Some(Err(e)) => {
+                                eprintln!("Stream error: {}", e);
+                                break;
+                            }
+                            None => break,
+                        }
+                    }
 
-    results
+                    (success, errors)
+                }
+            },
+        )
+        .await
 }
 
 #[tokio::main]
@@ -662,6 +712,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         eprintln!("Invalid protocol: {} (must be 'grpc' or 'rest')", args.protocol);
         std::process::exit(1);
     }
+    if !["disable", "prefer", "require"].contains(&args.db_sslmode.as_str()) {
+        eprintln!("Invalid --db-sslmode: {} (must be 'disable', 'prefer', or 'require')", args.db_sslmode);
+        std::process::exit(1);
+    }
 
     let duration = parse_duration(&args.duration)?;
 
@@ -673,6 +727,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         &args.db_user,
         &args.db_pass,
         &args.db_name,
+        &args.db_sslmode,
+        args.db_ca_cert.as_deref(),
     )?;
     println!("Database pool created (max_size: 50)");
 
@@ -692,19 +748,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     println!("Concurrency: {} | Duration: {:?}", args.concurrency, duration);
 
+    // Managed shutdown: cancels when `duration` elapses, or immediately on
+    // Ctrl+C / SIGTERM, whichever comes first. Workers and the resource
+    // monitor all select on the same token.
+    let shutdown = Shutdown::new(duration);
+
+    // Optionally expose live Prometheus metrics for the duration of the run
+    let metrics = match &args.metrics_addr {
+        Some(addr) => {
+            let socket_addr: SocketAddr = addr
+                .parse()
+                .map_err(|e| format!("Invalid --metrics-addr {}: {}", addr, e))?;
+            let metrics = Metrics::new(&args.scenario, &args.protocol);
+            tokio::spawn(metrics::serve(socket_addr, metrics.clone()));
+            metrics.spawn_throughput_sampler(shutdown.token());
+            Some(metrics)
+        }
+        None => None,
+    };
+
+    // TLS settings for the gRPC channel, read from file once up front rather
+    // than in every worker.
+    let grpc_tls = GrpcTlsConfig {
+        ca_cert: args.grpc_tls_ca.as_deref().map(std::fs::read).transpose()?,
+        domain: args.grpc_tls_domain.clone(),
+    };
+
     // Run benchmark
     let results = match (args.scenario.as_str(), args.protocol.as_str()) {
         ("balance", "grpc") => {
             println!("Connected to gRPC server at {}", args.grpc_addr);
-            run_grpc_balance(&args.grpc_addr, account_ids, args.concurrency, duration).await
+            run_grpc_balance(&args.grpc_addr, account_ids, args.concurrency, &shutdown, args.rate, metrics.clone(), grpc_tls).await
         }
         ("balance", "rest") => {
             println!("Connected to REST server at {}", args.rest_addr);
-            run_rest_balance(&args.rest_addr, account_ids, args.concurrency, duration).await
+            run_rest_balance(&args.rest_addr, account_ids, args.concurrency, &shutdown, args.rate, metrics.clone()).await
         }
         ("stream", "grpc") => {
             println!("Connected to gRPC server at {}", args.grpc_addr);
-            run_grpc_stream(&args.grpc_addr, args.concurrency, duration, args.rate).await
+            run_grpc_stream(&args.grpc_addr, args.concurrency, &shutdown, args.rate, metrics.clone(), grpc_tls).await
         }
         ("stream", "rest") => {
             eprintln!("REST streaming not yet implemented in Rust client");
@@ -0,0 +1,157 @@
+//! Live Prometheus metrics, exposed on `/metrics` while a benchmark is running.
+//!
+//! Mirrors the gauge/counter registration pattern used by lite-rpc's postgres
+//! worker: a handful of metric handles are registered into a `Registry` up
+//! front, workers update them inline as they send requests, and a background
+//! task refreshes the throughput gauge once a second. A separate hyper server
+//! serves the registry's text encoding so Prometheus can scrape the client
+//! mid-run instead of waiting for `print_summary` at the end.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use prometheus::{Encoder, Gauge, Histogram, HistogramOpts, IntCounter, Opts, Registry, TextEncoder};
+
+pub struct Metrics {
+    registry: Registry,
+    requests_total: IntCounter,
+    requests_failed: IntCounter,
+    latency_seconds: Histogram,
+    throughput: Gauge,
+    completed: AtomicU64,
+}
+
+impl Metrics {
+    /// Build a registry of metrics const-labeled with this run's scenario/protocol.
+    pub fn new(scenario: &str, protocol: &str) -> Arc<Self> {
+        let registry = Registry::new();
+
+        let requests_total = IntCounter::with_opts(
+            Opts::new("benchmark_requests_total", "Total requests sent")
+                .const_label("scenario", scenario)
+                .const_label("protocol", protocol),
+        )
+        .expect("valid counter opts");
+        let requests_failed = IntCounter::with_opts(
+            Opts::new("benchmark_requests_failed_total", "Requests that returned an error")
+                .const_label("scenario", scenario)
+                .const_label("protocol", protocol),
+        )
+        .expect("valid counter opts");
+        // Default Prometheus buckets top out around 10s and start at 5ms, far
+        // coarser than the microsecond-to-millisecond RPCs this client measures.
+        let latency_seconds = Histogram::with_opts(
+            HistogramOpts::new("benchmark_latency_seconds", "Request latency in seconds")
+                .const_label("scenario", scenario)
+                .const_label("protocol", protocol)
+                .buckets(vec![
+                    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+                ]),
+        )
+        .expect("valid histogram opts");
+        let throughput = Gauge::with_opts(
+            Opts::new("benchmark_throughput_rps", "Requests/sec over the trailing 1s window")
+                .const_label("scenario", scenario)
+                .const_label("protocol", protocol),
+        )
+        .expect("valid gauge opts");
+
+        registry
+            .register(Box::new(requests_total.clone()))
+            .expect("unique metric name");
+        registry
+            .register(Box::new(requests_failed.clone()))
+            .expect("unique metric name");
+        registry
+            .register(Box::new(latency_seconds.clone()))
+            .expect("unique metric name");
+        registry
+            .register(Box::new(throughput.clone()))
+            .expect("unique metric name");
+
+        Arc::new(Self {
+            registry,
+            requests_total,
+            requests_failed,
+            latency_seconds,
+            throughput,
+            completed: AtomicU64::new(0),
+        })
+    }
+
+    /// Record one completed request. Called inline from each worker's send loop.
+    pub fn record(&self, latency: Duration, success: bool) {
+        self.requests_total.inc();
+        self.latency_seconds.observe(latency.as_secs_f64());
+        if !success {
+            self.requests_failed.inc();
+        }
+        self.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Spawn the background task that refreshes the throughput gauge every
+    /// second until `shutdown` fires.
+    pub fn spawn_throughput_sampler(self: &Arc<Self>, shutdown: tokio_util::sync::CancellationToken) {
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            let mut last = 0u64;
+            let mut ticker = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = ticker.tick() => {
+                        let now = metrics.completed.load(Ordering::Relaxed);
+                        metrics.throughput.set((now.saturating_sub(last)) as f64);
+                        last = now;
+                    }
+                }
+            }
+        });
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let encoder = TextEncoder::new();
+        let families = self.registry.gather();
+        let mut buf = Vec::new();
+        encoder
+            .encode(&families, &mut buf)
+            .expect("prometheus text encoding");
+        buf
+    }
+}
+
+async fn handle(metrics: Arc<Metrics>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.uri().path() == "/metrics" {
+        Ok(Response::new(Body::from(metrics.encode())))
+    } else {
+        let mut response = Response::new(Body::from("not found"));
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        Ok(response)
+    }
+}
+
+/// Serve `/metrics` in Prometheus text format until the process exits.
+pub async fn serve(addr: SocketAddr, metrics: Arc<Metrics>) {
+    let make_svc = make_service_fn(move |_conn| {
+        let metrics = metrics.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(metrics.clone(), req))) }
+    });
+
+    let server = match Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_svc),
+        Err(e) => {
+            eprintln!("Failed to bind metrics server to {}: {}", addr, e);
+            return;
+        }
+    };
+
+    println!("Metrics listening on http://{}/metrics", addr);
+    if let Err(e) = server.await {
+        eprintln!("Metrics server error: {}", e);
+    }
+}
@@ -0,0 +1,68 @@
+//! Managed shutdown: a single cancellation point that workers and the
+//! resource monitor all select on, instead of the bare `tokio::spawn` +
+//! `AtomicBool` + sleep a run used to hand-roll. Cancellation fires on
+//! whichever comes first: the run duration elapsing, Ctrl+C, or (on Unix)
+//! SIGTERM. Workers stop issuing new requests as soon as it fires but the
+//! caller still gets a bounded grace period to let in-flight requests land
+//! before the run is drained and reported.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+/// How long to wait for in-flight requests to finish after cancellation
+/// fires before giving up and reporting whatever was gathered.
+pub const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+pub struct Shutdown {
+    token: CancellationToken,
+}
+
+impl Shutdown {
+    /// Start the timers: cancels after `duration`, or immediately on a
+    /// shutdown signal, whichever happens first.
+    pub fn new(duration: Duration) -> Self {
+        let token = CancellationToken::new();
+
+        let timer_token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            timer_token.cancel();
+        });
+
+        let signal_token = token.clone();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            println!("\nShutdown signal received, draining in-flight requests...");
+            signal_token.cancel();
+        });
+
+        Self { token }
+    }
+
+    /// A clone of the underlying token, to hand to workers and the resource monitor.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Resolves once shutdown has been triggered by the timer or a signal.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await;
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate()).expect("install SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,262 @@
+//! Generic bounded worker pool shared by every scenario's runner, modeled on
+//! the skytable benchmark's Workpool. The three `run_*` functions in `main`
+//! used to each re-implement the same machinery by hand: spawn `concurrency`
+//! tasks, duplicate the resource-monitor block, wait for shutdown, then drain
+//! and merge histograms. `WorkerPool` owns that lifecycle once; a scenario
+//! only supplies a `connect` closure (build a worker's client) and a `work`
+//! closure (what that worker does with it until cancelled). A `connect`
+//! failure is recorded as a single counted error sample instead of silently
+//! dropping the worker, so a server that's down shows up as errors rather
+//! than an artificially short run.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use sysinfo::System;
+use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+
+use crate::histogram::Histogram;
+use crate::shutdown::{self, Shutdown};
+
+#[derive(Debug, Default)]
+pub struct Results {
+    success: Histogram,
+    errors: Histogram,
+    pub(crate) start_time: Option<Instant>,
+    pub(crate) end_time: Option<Instant>,
+    cpu_samples: Vec<f32>,
+    mem_samples: Vec<u64>,
+}
+
+impl Results {
+    /// Fold another worker's histograms into this one.
+    fn merge(&mut self, success: &Histogram, errors: &Histogram) {
+        self.success.merge(success);
+        self.errors.merge(errors);
+    }
+
+    pub fn success_count(&self) -> u64 {
+        self.success.count()
+    }
+
+    pub fn error_count(&self) -> u64 {
+        self.errors.count()
+    }
+
+    pub fn percentile(&self, p: f64) -> Duration {
+        self.success.percentile(p)
+    }
+
+    pub fn avg_latency(&self) -> Duration {
+        self.success.mean()
+    }
+
+    pub fn min_latency(&self) -> Duration {
+        self.success.min()
+    }
+
+    pub fn max_latency(&self) -> Duration {
+        self.success.max()
+    }
+
+    pub fn throughput(&self) -> f64 {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => {
+                let duration = end.duration_since(start).as_secs_f64();
+                if duration > 0.0 {
+                    self.success_count() as f64 / duration
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn avg_cpu(&self) -> f32 {
+        if self.cpu_samples.is_empty() {
+            return 0.0;
+        }
+        self.cpu_samples.iter().sum::<f32>() / self.cpu_samples.len() as f32
+    }
+
+    pub fn avg_mem_mb(&self) -> f64 {
+        if self.mem_samples.is_empty() {
+            return 0.0;
+        }
+        let avg_bytes = self.mem_samples.iter().sum::<u64>() as f64 / self.mem_samples.len() as f64;
+        avg_bytes / 1024.0 / 1024.0
+    }
+
+    pub fn peak_mem_mb(&self) -> f64 {
+        self.mem_samples.iter().max().copied().unwrap_or(0) as f64 / 1024.0 / 1024.0
+    }
+
+    pub fn print_summary(&self, scenario: &str, protocol: &str, concurrency: usize) {
+        let duration = match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => end.duration_since(start),
+            _ => Duration::ZERO,
+        };
+
+        println!();
+        println!("Benchmark: {} / {}", scenario, protocol);
+        println!("Duration: {:?} | Concurrency: {}", duration, concurrency);
+        let total_requests = self.success_count() + self.error_count();
+        println!("---------------------------------");
+        println!("Requests:    {}", total_requests);
+        println!("Throughput:  {:.2} req/s", self.throughput());
+        println!("Latency:");
+        println!("  p50:  {:?}", self.percentile(50.0));
+        println!("  p90:  {:?}", self.percentile(90.0));
+        println!("  p99:  {:?}", self.percentile(99.0));
+        println!("  avg:  {:?}", self.avg_latency());
+        println!("  min:  {:?}", self.min_latency());
+        println!("  max:  {:?}", self.max_latency());
+        println!(
+            "Errors:      {} ({:.2}%)",
+            self.error_count(),
+            self.error_count() as f64 / total_requests.max(1) as f64 * 100.0
+        );
+        println!("Resources:");
+        println!("  CPU avg:   {:.1}%", self.avg_cpu());
+        println!("  Mem avg:   {:.1} MB", self.avg_mem_mb());
+        println!("  Mem peak:  {:.1} MB", self.peak_mem_mb());
+    }
+}
+
+/// Await all worker handles, merging each one's histograms as it finishes,
+/// but give up once `grace_period` elapses after cancellation so a worker
+/// stuck on a single slow request can't hang the whole report.
+async fn collect_worker_histograms(
+    handles: Vec<tokio::task::JoinHandle<(Histogram, Histogram)>>,
+    grace_period: Duration,
+) -> (Histogram, Histogram) {
+    let mut success = Histogram::new();
+    let mut errors = Histogram::new();
+    let mut pending: FuturesUnordered<_> = handles.into_iter().collect();
+    let deadline = tokio::time::sleep(grace_period);
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            next = pending.next() => {
+                match next {
+                    Some(Ok((s, e))) => {
+                        success.merge(&s);
+                        errors.merge(&e);
+                    }
+                    Some(Err(_)) => {}
+                    None => break,
+                }
+            }
+            _ = &mut deadline => {
+                if !pending.is_empty() {
+                    eprintln!(
+                        "Shutdown grace period elapsed with {} worker(s) still in flight; reporting partial results",
+                        pending.len()
+                    );
+                }
+                break;
+            }
+        }
+    }
+
+    (success, errors)
+}
+
+/// Sample process CPU/memory every 100ms into the given accumulators until `token` is cancelled.
+fn spawn_resource_monitor(
+    token: CancellationToken,
+    cpu_samples: Arc<tokio::sync::Mutex<Vec<f32>>>,
+    mem_samples: Arc<tokio::sync::Mutex<Vec<u64>>>,
+) {
+    tokio::spawn(async move {
+        let mut sys = System::new_all();
+        let pid = sysinfo::get_current_pid().unwrap();
+        let mut interval = interval(Duration::from_millis(100));
+
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = interval.tick() => {
+                    sys.refresh_all();
+                    if let Some(process) = sys.process(pid) {
+                        cpu_samples.lock().await.push(process.cpu_usage());
+                        mem_samples.lock().await.push(process.memory());
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Bounded pool of `concurrency` workers. Each scenario supplies `connect`
+/// (build one worker's client) and `work` (that worker's request loop); the
+/// pool handles spawning, the shared resource sampler, waiting out
+/// `shutdown`, and merging every worker's histograms into a `Results`.
+pub struct WorkerPool {
+    concurrency: usize,
+}
+
+impl WorkerPool {
+    pub fn new(concurrency: usize) -> Self {
+        Self { concurrency }
+    }
+
+    /// Run the pool to completion. A `connect` failure is recorded as a
+    /// single counted error sample rather than dropping the worker silently.
+    pub async fn run<C, ConnectFut, WorkFut>(
+        self,
+        shutdown: &Shutdown,
+        connect: impl Fn(usize) -> ConnectFut + Send + Sync + 'static,
+        work: impl Fn(usize, C, CancellationToken) -> WorkFut + Send + Sync + 'static,
+    ) -> Results
+    where
+        C: Send + 'static,
+        ConnectFut: Future<Output = Result<C, String>> + Send + 'static,
+        WorkFut: Future<Output = (Histogram, Histogram)> + Send + 'static,
+    {
+        let mut results = Results::default();
+
+        let cpu_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let mem_samples = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        spawn_resource_monitor(shutdown.token(), cpu_samples.clone(), mem_samples.clone());
+
+        results.start_time = Some(Instant::now());
+
+        let connect = Arc::new(connect);
+        let work = Arc::new(work);
+
+        let mut handles = Vec::with_capacity(self.concurrency);
+        for worker_index in 0..self.concurrency {
+            let token = shutdown.token();
+            let connect = connect.clone();
+            let work = work.clone();
+
+            handles.push(tokio::spawn(async move {
+                match connect(worker_index).await {
+                    Ok(client) => work(worker_index, client, token).await,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        let mut errors = Histogram::new();
+                        errors.record(0);
+                        (Histogram::new(), errors)
+                    }
+                }
+            }));
+        }
+
+        shutdown.cancelled().await;
+        let (success, errors) = collect_worker_histograms(handles, shutdown::GRACE_PERIOD).await;
+        results.merge(&success, &errors);
+
+        results.end_time = Some(Instant::now());
+        results.cpu_samples = cpu_samples.lock().await.clone();
+        results.mem_samples = mem_samples.lock().await.clone();
+
+        results
+    }
+}